@@ -0,0 +1,5 @@
+/*!
+A library for reading and writing Minecraft's region (`.mca`) file format, known as Anvil.
+*/
+pub mod raw;
+pub mod nbt;
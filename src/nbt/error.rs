@@ -0,0 +1,51 @@
+use std::fmt;
+
+/// An error type used when parsing an NBT tag stream, allows for custom error handling
+#[derive(Debug)]
+pub enum NbtError {
+    UnexpectedEof(NbtErrData),
+    UnknownTagIdErr(NbtErrData),
+    InvalidUtf8Err(NbtErrData),
+}
+
+impl NbtError {
+    /// Returns a new error denoting that the stream ended before the number of bytes needed to
+    /// read the next field (given by `needed`) were available (only `available` remained).
+    pub fn throw_eof_err(needed: usize, available: usize) -> NbtError {
+        let info = NbtErrData { tag_id: 0, needed, available };
+        NbtError::UnexpectedEof(info)
+    }
+    /// Returns a new error denoting that a tag id byte did not match any of the known NBT tag
+    /// types.
+    pub fn throw_unknown_tag_err(tag_id: u8) -> NbtError {
+        let info = NbtErrData { tag_id: tag_id as usize, needed: 0, available: 0 };
+        NbtError::UnknownTagIdErr(info)
+    }
+    /// Returns a new error denoting that a tag or compound entry name was not valid UTF-8.
+    pub fn throw_invalid_utf8_err(tag_id: u8) -> NbtError {
+        let info = NbtErrData { tag_id: tag_id as usize, needed: 0, available: 0 };
+        NbtError::InvalidUtf8Err(info)
+    }
+}
+
+/// A helper structure to store information on potential errors when parsing an NBT tag stream
+#[derive(Debug)]
+pub struct NbtErrData {
+    tag_id: usize,
+    needed: usize,
+    available: usize,
+}
+
+impl fmt::Display for NbtError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NbtError::UnexpectedEof(info) =>
+                write!(f, "Unexpected end of NBT stream, needed {} bytes but only {} were available",
+                    info.needed, info.available),
+            NbtError::UnknownTagIdErr(info) =>
+                write!(f, "Unknown NBT tag id: {}", info.tag_id),
+            NbtError::InvalidUtf8Err(info) =>
+                write!(f, "Invalid UTF-8 encountered reading the name of tag id: {}", info.tag_id),
+        }
+    }
+}
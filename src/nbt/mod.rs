@@ -0,0 +1,189 @@
+/*!
+A small reader for Minecraft's big-endian NBT tag stream, the format chunk bodies are encoded in
+once decompressed (see [`crate::raw::RawChunk::decompress`]).
+*/
+mod error;
+
+pub use error::*;
+
+const TAG_END: u8 = 0;
+const TAG_BYTE: u8 = 1;
+const TAG_SHORT: u8 = 2;
+const TAG_INT: u8 = 3;
+const TAG_LONG: u8 = 4;
+const TAG_FLOAT: u8 = 5;
+const TAG_DOUBLE: u8 = 6;
+const TAG_BYTE_ARRAY: u8 = 7;
+const TAG_STRING: u8 = 8;
+const TAG_LIST: u8 = 9;
+const TAG_COMPOUND: u8 = 10;
+const TAG_INT_ARRAY: u8 = 11;
+const TAG_LONG_ARRAY: u8 = 12;
+
+/// A single parsed NBT tag's payload. Named compound entries keep their name alongside the value;
+/// list elements are unnamed, matching the NBT spec.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Nbt {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    ByteArray(Vec<i8>),
+    String(String),
+    List(Vec<Nbt>),
+    Compound(Vec<(String, Nbt)>),
+    IntArray(Vec<i32>),
+    LongArray(Vec<i64>),
+}
+
+impl Nbt {
+    /// Parses a full NBT tag stream, as found in a decompressed chunk body, returning the
+    /// payload of the single root tag (almost always a `Compound`). The root tag's own name is
+    /// discarded, since callers only ever care about its fields.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err(NbtError)` if the stream ends early or a tag id byte does not match a known
+    /// NBT tag type, instead of panicking on malformed input.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Nbt, NbtError> {
+        let mut reader = Reader { buf: bytes, pos: 0 };
+        let tag_id = reader.read_u8()?;
+        let _name = reader.read_name(tag_id)?;
+        reader.read_payload(tag_id)
+    }
+
+    /// Looks up a single named field of this tag, if this tag is a `Compound` and contains it.
+    pub fn get(&self, name: &str) -> Option<&Nbt> {
+        match self {
+            Nbt::Compound(fields) => fields.iter().find(|(n, _)| n == name).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// Walks a `/`-separated path of compound field names, e.g. `"Level/Sections"`, returning the
+    /// tag at the end of the path if every segment along the way resolves to a `Compound` field.
+    pub fn path(&self, path: &str) -> Option<&Nbt> {
+        path.split('/').filter(|segment| !segment.is_empty()).try_fold(self, Nbt::get)
+    }
+}
+
+struct Reader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], NbtError> {
+        let end = self.pos + len;
+        let slice = self.buf.get(self.pos..end)
+            .ok_or_else(|| NbtError::throw_eof_err(len, self.buf.len().saturating_sub(self.pos)))?;
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn read_u8(&mut self) -> Result<u8, NbtError> {
+        Ok(self.take(1)?[0])
+    }
+    fn read_i8(&mut self) -> Result<i8, NbtError> {
+        Ok(self.read_u8()? as i8)
+    }
+    fn read_i16(&mut self) -> Result<i16, NbtError> {
+        Ok(i16::from_be_bytes(self.take(2)?.try_into().unwrap()))
+    }
+    fn read_u16(&mut self) -> Result<u16, NbtError> {
+        Ok(u16::from_be_bytes(self.take(2)?.try_into().unwrap()))
+    }
+    fn read_i32(&mut self) -> Result<i32, NbtError> {
+        Ok(i32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+    fn read_i64(&mut self) -> Result<i64, NbtError> {
+        Ok(i64::from_be_bytes(self.take(8)?.try_into().unwrap()))
+    }
+    fn read_f32(&mut self) -> Result<f32, NbtError> {
+        Ok(f32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+    fn read_f64(&mut self) -> Result<f64, NbtError> {
+        Ok(f64::from_be_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn read_name(&mut self, tag_id: u8) -> Result<String, NbtError> {
+        let len = self.read_u16()? as usize;
+        let bytes = self.take(len)?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| NbtError::throw_invalid_utf8_err(tag_id))
+    }
+
+    /// Reads a big-endian `i32` element count, as found at the start of a byte/int/long array or
+    /// list payload, and checks it against the bytes actually left in the stream before handing it
+    /// back as a `Vec::with_capacity` size - a malformed or negative declared count (e.g.
+    /// `0xFFFFFFFF`) would otherwise cast to a huge `usize` and panic the allocation instead of
+    /// surfacing as a parse error.
+    fn read_element_count(&mut self) -> Result<usize, NbtError> {
+        let len = self.read_i32()?;
+        let available = self.buf.len().saturating_sub(self.pos);
+        if len < 0 || len as usize > available {
+            return Err(NbtError::throw_eof_err(len.max(0) as usize, available));
+        }
+        Ok(len as usize)
+    }
+
+    fn read_payload(&mut self, tag_id: u8) -> Result<Nbt, NbtError> {
+        match tag_id {
+            TAG_BYTE => Ok(Nbt::Byte(self.read_i8()?)),
+            TAG_SHORT => Ok(Nbt::Short(self.read_i16()?)),
+            TAG_INT => Ok(Nbt::Int(self.read_i32()?)),
+            TAG_LONG => Ok(Nbt::Long(self.read_i64()?)),
+            TAG_FLOAT => Ok(Nbt::Float(self.read_f32()?)),
+            TAG_DOUBLE => Ok(Nbt::Double(self.read_f64()?)),
+            TAG_BYTE_ARRAY => {
+                let len = self.read_element_count()?;
+                let mut values = Vec::with_capacity(len);
+                for _ in 0..len {
+                    values.push(self.read_i8()?);
+                }
+                Ok(Nbt::ByteArray(values))
+            }
+            TAG_STRING => Ok(Nbt::String(self.read_name(TAG_STRING)?)),
+            TAG_LIST => {
+                let element_id = self.read_u8()?;
+                let len = self.read_element_count()?;
+                let mut values = Vec::with_capacity(len);
+                for _ in 0..len {
+                    values.push(self.read_payload(element_id)?);
+                }
+                Ok(Nbt::List(values))
+            }
+            TAG_COMPOUND => {
+                let mut fields = Vec::new();
+                loop {
+                    let field_id = self.read_u8()?;
+                    if field_id == TAG_END {
+                        break;
+                    }
+                    let name = self.read_name(field_id)?;
+                    let value = self.read_payload(field_id)?;
+                    fields.push((name, value));
+                }
+                Ok(Nbt::Compound(fields))
+            }
+            TAG_INT_ARRAY => {
+                let len = self.read_element_count()?;
+                let mut values = Vec::with_capacity(len);
+                for _ in 0..len {
+                    values.push(self.read_i32()?);
+                }
+                Ok(Nbt::IntArray(values))
+            }
+            TAG_LONG_ARRAY => {
+                let len = self.read_element_count()?;
+                let mut values = Vec::with_capacity(len);
+                for _ in 0..len {
+                    values.push(self.read_i64()?);
+                }
+                Ok(Nbt::LongArray(values))
+            }
+            other => Err(NbtError::throw_unknown_tag_err(other)),
+        }
+    }
+}
@@ -2,45 +2,283 @@ mod raw_schema;
 mod raw_error;
 mod unpack;
 mod pack;
+mod report;
 
 pub use raw_schema::*;
 pub use raw_error::*;
+pub use report::*;
 
+use std::io::{Read, Write};
 use std::result::Result;
 
-/// Used to denote the compression in each chunk, will be `Compression::None` if the chunk is blank 
+use flate2::read::{GzDecoder, ZlibDecoder};
+use flate2::write::{GzEncoder, ZlibEncoder};
+use flate2::Compression as Flate2Level;
+use lz4_flex::block::{compress_prepend_size, decompress_size_prepended};
+
+/// Used to denote the compression in each chunk, will be `Compression::None` if the chunk is blank
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Compression {
     GZip,
     ZLib,
+    /// Stored as-is, with no compression applied (compression scheme byte `3`).
+    Uncompressed,
+    /// Compressed with LZ4 block compression (compression scheme byte `4`).
+    Lz4,
+    /// Compressed by a server-specific codec identified by a namespaced key (compression scheme
+    /// byte `127`), e.g. a mod registering its own compressor. This crate has no way to actually
+    /// run an arbitrary custom codec, so chunks using it are only ever passed through unchanged -
+    /// see [`RawChunk::decompress`] and [`RawChunk::from_decompressed`].
+    Custom(String),
     None,
 }
 
-/// The compression format and along with the compressed chunk data. These make up the region. 
-pub type RawChunk = (Compression, Vec<u8>, u32); 
+/// The compression scheme and compressed body for a single chunk, along with its last-modified
+/// timestamp. These make up the region.
+#[derive(Debug, Clone)]
+pub struct RawChunk {
+    pub compression: Compression,
+    pub data: Vec<u8>,
+    pub timestamp: u32,
+    /// The sector (4096-byte block, counted from the start of the file) this chunk currently
+    /// occupies, as read from the posistion table. `0` for a chunk that has never been written
+    /// to a file, since sectors `0` and `1` are always the fixed-size tables.
+    pub sector_offset: usize,
+    /// Set when the chunk header's compression byte has its `0x80` high bit set, meaning `data`
+    /// is only a stub and the real body lives in an external `c.<x>.<z>.mcc` file alongside the
+    /// region, named from this chunk's `region_x`/`region_z`.
+    pub external: bool,
+    /// This chunk's region-relative X coordinate (`0..32`), i.e. its column in the 32x32 grid of
+    /// chunks that make up a region. Used together with `region_z` to name its `.mcc` sidecar.
+    pub region_x: u8,
+    /// This chunk's region-relative Z coordinate (`0..32`), i.e. its row in the 32x32 grid of
+    /// chunks that make up a region. Used together with `region_x` to name its `.mcc` sidecar.
+    pub region_z: u8,
+}
+
+impl RawChunk {
+    /// Inflates this chunk's body according to its recorded `Compression` scheme, returning the
+    /// raw NBT payload. An empty/`Compression::None` chunk is passed straight through.
+    ///
+    /// # Errors
+    ///
+    /// If the stored bytes are not a valid stream for the recorded scheme (e.g. a truncated or
+    /// corrupt compressed body), returns `Err(RawError::DecompressErr(..))` carrying the
+    /// underlying decoder's message rather than panicking. The error's chunk index is always `0`,
+    /// since a lone chunk has no table position of its own; [`RawRegion::decompress_all`] reports
+    /// the chunk's real index.
+    pub fn decompress(&self) -> Result<Vec<u8>, RawError> {
+        decompress_chunk(self, 0)
+    }
+
+    /// Builds a `RawChunk` from an already-decompressed NBT payload, compressing it with the
+    /// given scheme. The returned chunk's timestamp defaults to `0`; set `timestamp` afterwards
+    /// if the original last-modified time needs to be preserved.
+    pub fn from_decompressed(data: &[u8], scheme: Compression) -> RawChunk {
+        let compressed = match &scheme {
+            Compression::GZip => {
+                let mut encoder = GzEncoder::new(Vec::new(), Flate2Level::default());
+                encoder.write_all(data).expect("writing to an in-memory encoder cannot fail");
+                encoder.finish().expect("finishing an in-memory encoder cannot fail")
+            }
+            Compression::ZLib => {
+                let mut encoder = ZlibEncoder::new(Vec::new(), Flate2Level::default());
+                encoder.write_all(data).expect("writing to an in-memory encoder cannot fail");
+                encoder.finish().expect("finishing an in-memory encoder cannot fail")
+            }
+            Compression::Uncompressed | Compression::None => data.to_vec(),
+            Compression::Lz4 => compress_prepend_size(data),
+            // No codec to run - the caller is expected to have already compressed `data` itself.
+            Compression::Custom(_) => data.to_vec(),
+        };
+        RawChunk {
+            compression: scheme,
+            data: compressed,
+            timestamp: 0,
+            sector_offset: 0,
+            external: false,
+            region_x: 0,
+            region_z: 0,
+        }
+    }
+}
+
+fn decompress_chunk(chunk: &RawChunk, index: usize) -> Result<Vec<u8>, RawError> {
+    match &chunk.compression {
+        Compression::GZip => decompress_with(GzDecoder::new(chunk.data.as_slice()), 1, index),
+        Compression::ZLib => decompress_with(ZlibDecoder::new(chunk.data.as_slice()), 2, index),
+        Compression::Uncompressed => Ok(chunk.data.clone()),
+        Compression::Lz4 => decompress_size_prepended(&chunk.data)
+            .map_err(|err| RawError::throw_decompress_err(index, 4, err.to_string())),
+        // No codec to run - handed back as-is, same as an uncompressed chunk.
+        Compression::Custom(_) => Ok(chunk.data.clone()),
+        Compression::None => Ok(chunk.data.clone()),
+    }
+}
+
+fn decompress_with<R: Read>(mut decoder: R, scheme: u8, index: usize) -> Result<Vec<u8>, RawError> {
+    let mut output = Vec::new();
+    decoder
+        .read_to_end(&mut output)
+        .map_err(|err| RawError::throw_decompress_err(index, scheme, err.to_string()))?;
+    Ok(output)
+}
+
+/// A chunk whose packed body is too large to store inline in a region file (more than 255
+/// sectors), produced by [`RawRegion::to_file`] alongside the region's own bytes. The caller is
+/// responsible for writing `data` out to a `c.<region_x>.<region_z>.mcc` file next to the region;
+/// this crate only computes what goes in it, since it never does file I/O itself.
+#[derive(Debug, Clone)]
+pub struct ExternalChunk {
+    pub region_x: u8,
+    pub region_z: u8,
+    /// The chunk's compressed body, with no region-style 5-byte header - the in-region stub's
+    /// compression byte already records the scheme, and the sidecar's length is implicit in its
+    /// file size.
+    pub data: Vec<u8>,
+}
+
+/// The output of [`RawRegion::to_file_with_checksums`]: the packed region file bytes, any chunks
+/// split out to external `.mcc` sidecars, and the `.crc` sidecar covering the region's own bytes.
+type ChecksummedRegionFile = (Vec<u8>, Vec<ExternalChunk>, Vec<u8>);
+
+/// Options controlling how [`RawRegion::from_file_recovering`] reacts to a chunk it can't parse.
+#[derive(Debug, Clone, Copy)]
+pub struct RecoveryOptions {
+    /// If set, a chunk that fails to parse has its timestamp zeroed along with its data, as if
+    /// its posistion-table record had been deleted outright. If unset, its original timestamp is
+    /// kept so the caller can still tell when the unreadable chunk was last written, even though
+    /// its data is gone either way.
+    pub drop_corrupt_chunks: bool,
+}
+
+impl RecoveryOptions {
+    /// Drops corrupt chunks' timestamps along with their data.
+    #[allow(clippy::should_implement_trait)]
+    pub fn default() -> RecoveryOptions {
+        RecoveryOptions { drop_corrupt_chunks: true }
+    }
+}
 
-/// A wrapper for a group of chunks in a region, these are usually grouped as such in region files. 
+/// A wrapper for a group of chunks in a region, these are usually grouped as such in region files.
 pub struct RawRegion {
-    pub chunks: Vec<RawChunk>, 
+    pub chunks: Vec<RawChunk>,
 }
 
 impl RawRegion {
-    pub fn from_file(file: &Vec<u8>, schema: &AnvilSchema) -> Result<RawRegion, RawError> {
+    pub fn from_file(file: &[u8], schema: &AnvilSchema) -> Result<RawRegion, RawError> {
         let min_size = schema.min_anvil_file_size;
         if file.len() < min_size {
             return Err(RawError::throw_file_size_err(min_size, file.len()));
         }
-        let header_table = unpack::get_posistion_table(&file, &schema);
-        let timestamp_table = unpack::get_timestamp_table(&file, &schema);
-        let chunks = unpack::get_chunks(&file, &header_table, &timestamp_table, &schema)?;
+        let header_table = unpack::get_posistion_table(file, schema)?;
+        let timestamp_table = unpack::get_timestamp_table(file, schema)?;
+        let chunks = unpack::get_chunks(file, &header_table, &timestamp_table, schema)?;
         Ok(RawRegion { chunks })
     }
-    pub fn to_file(&self, schema: &AnvilSchema) -> Result<Vec<u8>, RawError> {
+    /// Like [`RawRegion::from_file`], but never aborts on the first bad chunk. Every slot in the
+    /// posistion table is parsed independently, with any failures collected into the returned
+    /// `Vec<RawError>` (in table order) and their chunk replaced with a blank placeholder per
+    /// `options`, rather than the whole region being unreadable because of one damaged chunk.
+    /// Blanked chunks are naturally dropped and their sectors reclaimed the next time the
+    /// returned region is written back out with [`RawRegion::to_file`].
+    ///
+    /// # Errors
+    ///
+    /// Still returns a single `RawError` (with an empty region) if the file is too small to hold
+    /// the fixed posistion/timestamp tables at all, since there is nothing to recover per-chunk in
+    /// that case.
+    pub fn from_file_recovering(file: &[u8], schema: &AnvilSchema, options: RecoveryOptions) -> (RawRegion, Vec<RawError>) {
+        let min_size = schema.min_anvil_file_size;
+        if file.len() < min_size {
+            return (RawRegion { chunks: Vec::new() }, vec![RawError::throw_file_size_err(min_size, file.len())]);
+        }
+        // The length check above guarantees both fixed tables lie fully within `file`, so neither
+        // of these can fail here even though they're fallible in general.
+        let header_table = unpack::get_posistion_table(file, schema)
+            .expect("file is at least min_anvil_file_size bytes, checked above");
+        let timestamp_table = unpack::get_timestamp_table(file, schema)
+            .expect("file is at least min_anvil_file_size bytes, checked above");
+        let (chunks, errors) = unpack::get_chunks_recovering(file, &header_table, &timestamp_table, schema, &options);
+        (RawRegion { chunks }, errors)
+    }
+
+    /// Packs the region into file bytes ready to write out as a `.mca` file. Any chunk whose
+    /// packed body would exceed 255 sectors (~1 MiB) is left out of the returned bytes as a
+    /// 1-sector external stub instead, with its real body returned separately in the
+    /// `Vec<ExternalChunk>` for the caller to write to that chunk's `.mcc` sidecar.
+    pub fn to_file(&self, schema: &AnvilSchema) -> Result<(Vec<u8>, Vec<ExternalChunk>), RawError> {
+        let chunks = &self.chunks;
+        if chunks.len() != schema.chunks_per_region {
+            return Err(RawError::throw_no_chunks_err(chunks.len(), schema.chunks_per_region));
+        }
+        let (packed_chunks, external) = pack::pack_chunks(chunks, schema)?;
+        let header_table = pack::create_header_table(chunks, &packed_chunks, schema);
+        let chunk_sectors = pack::layout_chunk_sectors(chunks, &packed_chunks);
+        let mut file = header_table;
+        file.extend(chunk_sectors);
+        Ok((file, external))
+    }
+
+    /// Like [`RawRegion::to_file`], but also computes a CRC32 over each table slot's packed bytes
+    /// and returns it encoded as the raw bytes of a `<region>.crc` sidecar, so the caller can
+    /// write it out alongside the region and later confirm with [`RawRegion::verify_checksums`]
+    /// that it survived a copy or transfer intact - Anvil itself has no per-chunk checksum.
+    pub fn to_file_with_checksums(&self, schema: &AnvilSchema) -> Result<ChecksummedRegionFile, RawError> {
         let chunks = &self.chunks;
-        if chunks.len() != schema.chunks_per_region as usize {
+        if chunks.len() != schema.chunks_per_region {
             return Err(RawError::throw_no_chunks_err(chunks.len(), schema.chunks_per_region));
         }
-        let packed_chunks = pack::pack_chunks(chunks, &schema)?;
+        let (packed_chunks, external) = pack::pack_chunks(chunks, schema)?;
         let header_table = pack::create_header_table(chunks, &packed_chunks, schema);
-        Ok(Vec::new())
+        let chunk_sectors = pack::layout_chunk_sectors(chunks, &packed_chunks);
+        let checksums = pack::compute_checksums(&packed_chunks);
+        let sidecar = pack::encode_checksum_sidecar(&checksums);
+        let mut file = header_table;
+        file.extend(chunk_sectors);
+        Ok((file, external, sidecar))
+    }
+
+    /// Verifies a region file's bytes against a `.crc` sidecar previously produced by
+    /// [`RawRegion::to_file_with_checksums`]. See [`unpack::verify_checksums`] for exactly what is
+    /// checked.
+    pub fn verify_checksums(file: &[u8], sidecar: &[u8], schema: &AnvilSchema) -> Result<(), RawError> {
+        unpack::verify_checksums(file, sidecar, schema)
+    }
+
+    /// Inflates every chunk in the region, in posistion-table order, using each chunk's own
+    /// recorded `Compression` scheme.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first `RawError::DecompressErr` encountered, carrying the index of the
+    /// offending chunk as it appears in `self.chunks`.
+    pub fn decompress_all(&self) -> Result<Vec<Vec<u8>>, RawError> {
+        self.chunks
+            .iter()
+            .enumerate()
+            .map(|(index, chunk)| decompress_chunk(chunk, index))
+            .collect()
+    }
+
+    /// Scans a region file's raw bytes for corruption without requiring it to parse cleanly,
+    /// so a partially damaged `.mca` can be inspected instead of only getting a hard error from
+    /// `from_file`. See [`report::scan`] for exactly what is checked.
+    pub fn scan(file: &[u8], schema: &AnvilSchema) -> RegionReport {
+        report::scan(file, schema, true)
+    }
+
+    /// Strictly validates a region file's posistion table, failing at the first reserved-sector
+    /// violation, overlap, or duplicate offset found. See [`unpack::validate_layout`] for exactly
+    /// what is checked; prefer [`RawRegion::scan`] instead if a single damaged chunk shouldn't
+    /// stop the rest of the file from being inspected.
+    pub fn validate_layout(file: &[u8], schema: &AnvilSchema) -> Result<(), RawError> {
+        unpack::validate_layout(file, schema)
+    }
+
+    /// Repairs a region file's raw bytes according to `mode`, using [`RawRegion::scan`] to find
+    /// the chunks that need action.
+    pub fn repair(file: &[u8], schema: &AnvilSchema, mode: RepairMode) -> Vec<u8> {
+        report::repair(file, schema, mode)
     }
 }
@@ -2,84 +2,175 @@ use super::{
     AnvilSchema,
     RawError,
     RawChunk,
-    Compression
+    Compression,
+    ExternalChunk,
 };
 
-pub fn pack_chunks(chunks: &[RawChunk], schema: &AnvilSchema) -> Result<Vec<Vec<u8>>, RawError> {
-    let mut output = Vec::new();
-    for iter in 0..schema.chunks_per_region as usize {
-        let (compression, chunk, _timestamp) = &chunks[iter];
-        let new_chunk = format_chunk_data(&chunk, &compression, &schema);
-        output.push(new_chunk);
+/// The two fixed 4096-byte tables (chunk locations, then timestamps) that precede every region
+/// file's chunk sectors.
+const TABLE_SECTOR_COUNT: usize = 2;
+
+/// The largest sector count a posistion-table record can declare (its sector-count byte is a
+/// single byte) - a packed chunk bigger than this must go out as an external `.mcc` sidecar
+/// instead, with the in-region record reduced to a 1-sector stub.
+const MAX_INLINE_SECTORS: usize = 255;
+
+/// Packs every chunk's body, splitting out any chunk too large to store inline. Returns the
+/// in-region bytes for each table slot (a real packed chunk, or a 1-sector external stub) in
+/// table order, alongside the external payloads that need writing to their own `.mcc` sidecars.
+pub fn pack_chunks(chunks: &[RawChunk], schema: &AnvilSchema) -> Result<(Vec<Vec<u8>>, Vec<ExternalChunk>), RawError> {
+    let mut inline = Vec::new();
+    let mut external = Vec::new();
+    for chunk in &chunks[..schema.chunks_per_region] {
+        let RawChunk { compression, data, region_x, region_z, .. } = chunk;
+        let body = format_chunk_data(data, compression, schema);
+        if body.len() / schema.size_multiplier > MAX_INLINE_SECTORS {
+            inline.push(make_external_stub(compression, schema));
+            external.push(ExternalChunk { region_x: *region_x, region_z: *region_z, data: data.clone() });
+        } else {
+            inline.push(body);
+        }
     }
-    Ok(output)
+    Ok((inline, external))
+}
+
+/// The 1-sector in-region record left behind for a chunk moved to an external sidecar: a bare
+/// chunk header (its declared length only covers the compression byte, since the real length is
+/// implicit in the sidecar file's size) with the `0x80` high bit set on the compression byte to
+/// flag external storage.
+fn make_external_stub(compression: &Compression, schema: &AnvilSchema) -> Vec<u8> {
+    let mut header = make_header(0, compression, schema);
+    header[schema.chunk_header_compr_bytes.0] |= 0x80;
+    pad_to_sector(header, schema.size_multiplier)
 }
 
 fn format_chunk_data(chunk: &[u8], compression: &Compression, schema: &AnvilSchema) -> Vec<u8> {
-    if chunk.len() == 0 {
+    if chunk.is_empty() {
         return Vec::new();
     }
-    let new_chunk_data = get_packed_chunk(chunk, &schema);
-    let mut header = make_header(chunk.len(), compression, schema);
-    header.extend(new_chunk_data);
-    header
+    let mut body = make_header(chunk.len(), compression, schema);
+    body.extend_from_slice(chunk);
+    pad_to_sector(body, schema.size_multiplier)
 }
 
-fn get_packed_chunk(chunk: &[u8], schema: &AnvilSchema) -> Vec<u8> {
-    let final_size = next_multiple(chunk.len(), schema.size_multiplier);
-    let amount_to_add = final_size - chunk.len();
-    let mut packed_chunk = chunk.to_vec();
-    packed_chunk.extend(vec![0; amount_to_add]);
-    packed_chunk
+fn pad_to_sector(mut body: Vec<u8>, sector_size: usize) -> Vec<u8> {
+    let final_size = next_multiple(body.len(), sector_size);
+    body.resize(final_size, 0);
+    body
 }
 
-fn make_header(size: usize, compression: &Compression, schema: &AnvilSchema) -> Vec<u8> {
-    // Make the vec we'll splice the data into later 
-    let mut output_vec = vec![0; schema.chunk_starts_from];
-    // Get the positions for where to splice the data into 
+fn make_header(data_len: usize, compression: &Compression, schema: &AnvilSchema) -> Vec<u8> {
+    // Get the positions for where to splice the data into
     let (size_start, size_end) = schema.chunk_header_size_bytes;
-    let (compr_start, compr_end) = schema.chunk_header_compr_bytes;
-    // Get the data 
-    let size_bytes = make_byte_arr(size, size_start - size_end);
     let compr_bytes = make_compr_bytes(compression);
-    // Splice the data in and return 
+    // The declared length covers the compression byte(s) - just one for a built-in scheme, or a
+    // compression byte plus a length-prefixed key for `Compression::Custom` - plus the data that
+    // follows them.
+    let declared_len = compr_bytes.len() + data_len;
+    let size_bytes = make_byte_arr(declared_len, size_end - size_start);
+    // Make the vec we'll splice the size into, then append the (possibly multi-byte) compression
+    // header after it
+    let mut output_vec = vec![0; size_end];
     output_vec.splice(size_start..size_end, size_bytes);
-    output_vec.splice(compr_start..compr_end, compr_bytes);
+    output_vec.extend(compr_bytes);
     output_vec
 }
 
+/// Returns the indices of `chunks` in the order their packed sectors should be laid out in the
+/// file: chunks that already occupy a real sector (a nonzero `sector_offset`) come first, ascending
+/// by that offset, followed by chunks with no known offset - either never written, or just rebuilt
+/// via [`RawChunk::from_decompressed`], which always starts a chunk back at `sector_offset: 0` -
+/// broken by table index. Placing the `sector_offset: 0` group last means rebuilding one chunk in
+/// an existing region only ripples into the chunks that come after its old slot, rather than
+/// sorting the rebuilt chunk back to the front of the file and cascading every other chunk's
+/// position.
+fn placement_order(chunks: &[RawChunk]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..chunks.len()).filter(|&i| !chunks[i].data.is_empty()).collect();
+    order.sort_by_key(|&i| (chunks[i].sector_offset == 0, chunks[i].sector_offset, i));
+    order
+}
 
+/// Compacts the packed chunks into the first free run of sectors after the two fixed tables,
+/// in `placement_order`, and returns the concatenated chunk sectors ready to be appended directly
+/// after the posistion/timestamp tables.
+pub fn layout_chunk_sectors(chunks: &[RawChunk], packed_chunks: &[Vec<u8>]) -> Vec<u8> {
+    let mut body = Vec::new();
+    for index in placement_order(chunks) {
+        body.extend_from_slice(&packed_chunks[index]);
+    }
+    body
+}
 
 pub fn create_header_table(chunks: &[RawChunk], packed_chunks: &[Vec<u8>], schema: &AnvilSchema) -> Vec<u8> {
-    let mut pos_from_start = 0;
-    let mut new_pos_table: Vec<u8> = Vec::new();
-    let mut new_date_table: Vec<u8> = Vec::new();
-    for iter in 0..chunks.len() {
-        let (_compression, _chunk, timestamp) = &chunks[iter];
-        let packed_chunk = &packed_chunks[iter];
-        pos_from_start += schema.min_anvil_file_size + packed_chunk.len();
-        let mut new_record = vec![0; schema.posistion_table_record_len];
-        let (start_byte_start, start_byte_end) = schema.pos_table_start_bytes;
-        let (size_byte_start, size_byte_end) = schema.pos_table_size_bytes;
-        let start_bytes = make_byte_arr(pos_from_start, start_byte_start - start_byte_end);
-        let size_bytes = make_byte_arr(packed_chunk.len(), size_byte_start - size_byte_end);
-        new_record.splice(start_byte_start..start_byte_end, start_bytes);
-        new_record.splice(size_byte_start..size_byte_end, size_bytes);
-        new_pos_table.extend(new_record);
-
-        let new_date = make_byte_arr(*timestamp as usize, 4);
-        new_date_table.extend(new_date);
+    let mut pos_table = vec![0u8; chunks.len() * schema.posistion_table_record_len];
+    let mut next_free_sector = TABLE_SECTOR_COUNT;
+    for index in placement_order(chunks) {
+        let packed_chunk = &packed_chunks[index];
+        let sector_count = packed_chunk.len() / schema.size_multiplier;
+        let record = make_pos_record(next_free_sector, sector_count, schema);
+        let record_start = index * schema.posistion_table_record_len;
+        pos_table[record_start..record_start + schema.posistion_table_record_len].copy_from_slice(&record);
+        next_free_sector += sector_count;
     }
-    new_pos_table.extend(new_date_table);
-    new_pos_table
+
+    let mut timestamp_table = Vec::with_capacity(chunks.len() * 4);
+    for chunk in chunks {
+        timestamp_table.extend(make_byte_arr(chunk.timestamp as usize, 4));
+    }
+
+    let mut header_table = pos_table;
+    header_table.extend(timestamp_table);
+    header_table
+}
+
+/// The length in bytes of a single record in a `.crc` sidecar: one big-endian CRC32 per chunk
+/// table slot, in table order - mirroring the posistion/timestamp tables' own one-fixed-width-
+/// record-per-slot layout.
+const CRC_RECORD_LEN: usize = 4;
+
+/// Computes a CRC32 over each table slot's in-region bytes, in table order, exactly as
+/// `pack_chunks` returned them - so a `.crc` sidecar built from this matches what ends up on disk,
+/// including a chunk's 1-sector external stub if it was moved to a `.mcc` sidecar instead of
+/// being stored inline.
+pub fn compute_checksums(packed_chunks: &[Vec<u8>]) -> Vec<u32> {
+    packed_chunks.iter().map(|chunk| crc32fast::hash(chunk)).collect()
 }
 
+/// Encodes a table of per-chunk CRC32s (in table order, as returned by [`compute_checksums`])
+/// into the raw bytes of a `<region>.crc` sidecar file.
+pub fn encode_checksum_sidecar(checksums: &[u32]) -> Vec<u8> {
+    let mut sidecar = Vec::with_capacity(checksums.len() * CRC_RECORD_LEN);
+    for crc in checksums {
+        sidecar.extend_from_slice(&crc.to_be_bytes());
+    }
+    sidecar
+}
 
+fn make_pos_record(sector_offset: usize, sector_count: usize, schema: &AnvilSchema) -> Vec<u8> {
+    let mut record = vec![0; schema.posistion_table_record_len];
+    let (start_byte_start, start_byte_end) = schema.pos_table_start_bytes;
+    let (size_byte_start, size_byte_end) = schema.pos_table_size_bytes;
+    let offset_bytes = make_byte_arr(sector_offset, start_byte_end - start_byte_start);
+    let count_bytes = make_byte_arr(sector_count, size_byte_end - size_byte_start);
+    record.splice(start_byte_start..start_byte_end, offset_bytes);
+    record.splice(size_byte_start..size_byte_end, count_bytes);
+    record
+}
 
 fn make_compr_bytes(compr: &Compression) -> Vec<u8> {
     match compr {
         Compression::GZip => vec![1],
         Compression::ZLib => vec![2],
+        Compression::Uncompressed => vec![3],
+        Compression::Lz4 => vec![4],
+        // A namespaced key identifying the custom codec, NBT-string-style: a 2-byte big-endian
+        // length followed by its UTF-8 bytes.
+        Compression::Custom(key) => {
+            let mut bytes = vec![127];
+            bytes.extend((key.len() as u16).to_be_bytes());
+            bytes.extend(key.as_bytes());
+            bytes
+        }
         Compression::None => vec![0]
     }
 }
@@ -96,5 +187,5 @@ fn make_byte_arr(num: usize, length: usize) -> Vec<u8> {
 
 fn next_multiple(num: usize, base: usize) -> usize {
     let multiple = num as f64 / base as f64;
-    return base * (multiple.ceil() as usize);
+    base * (multiple.ceil() as usize)
 }
@@ -1,4 +1,6 @@
-/// An error type used when unpacking/packing an anvil format file, allows for custom error handling 
+use std::fmt;
+
+/// An error type used when unpacking/packing an anvil format file, allows for custom error handling
 #[derive(Debug)]
 pub enum RawError {
     Pack,
@@ -7,6 +9,25 @@ pub enum RawError {
     UnpackFileSizeErr(RawErrData),
     UnpackChunkPosErr(RawErrData),
     UnpackChunkHeaderErr(RawErrData),
+    /// A parser tried to slice a range of bytes that falls outside the buffer it was reading
+    /// from, e.g. because the file was truncated.
+    UnpackSliceErr(RawSliceErrData),
+
+    /// A chunk's compressed body could not be inflated, either because it is truncated or
+    /// because the bytes are not a valid stream for the compression scheme recorded in its header.
+    DecompressErr(RawDecompressErrData),
+
+    /// A posistion-table record's offset falls inside the first two reserved 4 KiB sectors (the
+    /// posistion and timestamp tables), which no chunk may occupy.
+    ReservedSectorErr(RawReservedSectorErrData),
+    /// Two posistion-table records declare byte ranges that overlap each other.
+    ChunkOverlapErr(RawOverlapErrData),
+    /// Two posistion-table records, sorted by offset, declare the exact same offset.
+    NonIncrementalOffsetErr(RawOverlapErrData),
+
+    /// A chunk's CRC32, recomputed from the region file, does not match the one recorded for it
+    /// in a `.crc` sidecar.
+    ChunkChecksumErr(RawChecksumErrData),
 }
 
 impl RawError {
@@ -46,15 +67,74 @@ impl RawError {
         let info = RawErrData {
             chunk_index: 0,
             specified_val: no_chunks,
-            min_val: req_number as usize,
-            max_val: req_number as usize,
+            min_val: req_number,
+            max_val: req_number,
         };
         RawError::PackNoChunksErr(info)
     }
+
+    /// Returns a new error denoting that the requested byte range falls outside the buffer being
+    /// read, taking the requested range and the actual length of the buffer.
+    pub fn throw_slice_err(start: usize, end: usize, buf_len: usize) -> RawError {
+        let info = RawSliceErrData { start, end, buf_len };
+        RawError::UnpackSliceErr(info)
+    }
+
+    /// Returns a new error denoting that a chunk's compressed body could not be decompressed,
+    /// taking the index of the chunk as it appears in the posistion table, the raw compression
+    /// scheme byte it was read with, and the underlying error message from the decoder.
+    pub fn throw_decompress_err(index: usize, scheme: u8, message: String) -> RawError {
+        let info = RawDecompressErrData {
+            chunk_index: index,
+            scheme,
+            message,
+        };
+        RawError::DecompressErr(info)
+    }
+
+    // Layout validation errors
+
+    /// Returns a new error denoting that a chunk's declared range falls inside the reserved
+    /// posistion/timestamp tables, taking the chunk's table index and its `[start, end)` range.
+    pub fn throw_reserved_sector_err(index: usize, start: usize, end: usize) -> RawError {
+        let info = RawReservedSectorErrData { chunk_index: index, chunk_range: (start, end) };
+        RawError::ReservedSectorErr(info)
+    }
+
+    /// Returns a new error denoting that two chunks' declared ranges overlap, taking both chunks'
+    /// table indices and `[start, end)` ranges.
+    pub fn throw_chunk_overlap_err(index: usize, range: (usize, usize), other_index: usize, other_range: (usize, usize)) -> RawError {
+        let info = RawOverlapErrData {
+            chunk_index: index,
+            chunk_range: range,
+            other_chunk_index: other_index,
+            other_chunk_range: other_range,
+        };
+        RawError::ChunkOverlapErr(info)
+    }
+
+    /// Returns a new error denoting that two chunks, sorted by offset, declare the exact same
+    /// offset, taking both chunks' table indices and `[start, end)` ranges.
+    pub fn throw_non_incremental_offset_err(index: usize, range: (usize, usize), other_index: usize, other_range: (usize, usize)) -> RawError {
+        let info = RawOverlapErrData {
+            chunk_index: index,
+            chunk_range: range,
+            other_chunk_index: other_index,
+            other_chunk_range: other_range,
+        };
+        RawError::NonIncrementalOffsetErr(info)
+    }
+
+    /// Returns a new error denoting that a chunk's CRC32 did not match the one recorded for it in
+    /// a `.crc` sidecar, taking the chunk's table index and its expected/actual CRC32.
+    pub fn throw_checksum_err(index: usize, expected: u32, actual: u32) -> RawError {
+        let info = RawChecksumErrData { chunk_index: index, expected, actual };
+        RawError::ChunkChecksumErr(info)
+    }
 }
 
-/// A helper structure to store information on potential errors when processing 
-/// a raw anvil file 
+/// A helper structure to store information on potential errors when processing
+/// a raw anvil file
 #[derive(Debug)]
 pub struct RawErrData {
     chunk_index: usize,
@@ -62,3 +142,94 @@ pub struct RawErrData {
     min_val: usize,
     max_val: usize,
 }
+
+/// A helper structure to store information on a chunk whose compressed body failed to decompress
+#[derive(Debug)]
+pub struct RawDecompressErrData {
+    chunk_index: usize,
+    scheme: u8,
+    message: String,
+}
+
+/// A helper structure to store information on a byte range that fell outside the buffer it was
+/// requested from
+#[derive(Debug)]
+pub struct RawSliceErrData {
+    start: usize,
+    end: usize,
+    buf_len: usize,
+}
+
+/// A helper structure to store information on a chunk whose declared range falls inside the
+/// reserved posistion/timestamp tables
+#[derive(Debug)]
+pub struct RawReservedSectorErrData {
+    chunk_index: usize,
+    chunk_range: (usize, usize),
+}
+
+/// A helper structure to store information on two chunks whose declared ranges conflict, either
+/// because they overlap or because they declare the same offset
+#[derive(Debug)]
+pub struct RawOverlapErrData {
+    chunk_index: usize,
+    chunk_range: (usize, usize),
+    other_chunk_index: usize,
+    other_chunk_range: (usize, usize),
+}
+
+/// A helper structure to store information on a chunk whose CRC32 didn't match the one recorded
+/// for it in a `.crc` sidecar
+#[derive(Debug)]
+pub struct RawChecksumErrData {
+    chunk_index: usize,
+    expected: u32,
+    actual: u32,
+}
+
+impl fmt::Display for RawError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RawError::Pack => write!(f, "failed to pack region"),
+            RawError::PackNoChunksErr(info) => write!(
+                f, "expected {} chunks to pack a region, got {}", info.min_val, info.specified_val,
+            ),
+            RawError::UnpackFileSizeErr(info) => write!(
+                f, "file is too small to be a region: needed at least {} bytes, got {}",
+                info.min_val, info.specified_val,
+            ),
+            RawError::UnpackChunkPosErr(info) => write!(
+                f, "chunk {} declares an end offset of {}, which falls outside the file ({} bytes)",
+                info.chunk_index, info.specified_val, info.max_val,
+            ),
+            RawError::UnpackChunkHeaderErr(info) => write!(
+                f, "chunk {}'s header declares a length of {}, larger than its {}-byte allocation",
+                info.chunk_index, info.specified_val, info.max_val,
+            ),
+            RawError::UnpackSliceErr(info) => write!(
+                f, "tried to read bytes {}..{} from a buffer {} bytes long",
+                info.start, info.end, info.buf_len,
+            ),
+            RawError::DecompressErr(info) => write!(
+                f, "chunk {} failed to decompress under compression scheme {}: {}",
+                info.chunk_index, info.scheme, info.message,
+            ),
+            RawError::ReservedSectorErr(info) => write!(
+                f, "chunk {} occupies bytes {:?}, which overlap the reserved posistion/timestamp tables",
+                info.chunk_index, info.chunk_range,
+            ),
+            RawError::ChunkOverlapErr(info) => write!(
+                f, "chunk {} ({:?}) overlaps chunk {} ({:?})",
+                info.chunk_index, info.chunk_range, info.other_chunk_index, info.other_chunk_range,
+            ),
+            RawError::NonIncrementalOffsetErr(info) => write!(
+                f, "chunk {} ({:?}) declares the same offset as chunk {} ({:?})",
+                info.chunk_index, info.chunk_range, info.other_chunk_index, info.other_chunk_range,
+            ),
+            RawError::ChunkChecksumErr(info) => write!(
+                f, "chunk {}'s checksum did not match: expected {:08x}, got {:08x}",
+                info.chunk_index, info.expected, info.actual,
+            ),
+        }
+    }
+}
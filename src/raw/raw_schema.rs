@@ -32,7 +32,7 @@ impl AnvilSchema {
     /// Correct as of last build. 
     /// 
     /// # Example
-    /// ```
+    /// ```no_run
     /// use anvil_lib::raw;
     /// use std::fs;
     /// 
@@ -43,6 +43,7 @@ impl AnvilSchema {
     ///     raw::RawRegion::from_file(&file, &schema);
     /// }
     /// ```
+    #[allow(clippy::should_implement_trait)]
     pub fn default() -> AnvilSchema {
         AnvilSchema {
             chunks_per_region: 1024,
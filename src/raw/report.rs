@@ -0,0 +1,223 @@
+use crate::nbt::Nbt;
+
+use super::unpack::{get_posistion_table, make_usize_from_bytes, slice};
+use super::{AnvilSchema, Compression, RawChunk};
+
+/// Every required NBT tag a well-formed chunk's `Level` compound is expected to carry, checked by
+/// [`scan`] when asked to validate NBT as well as the raw layout.
+const REQUIRED_LEVEL_TAGS: [&str; 2] = ["xPos", "zPos"];
+
+/// The result of [`scan`]ning a region file for corruption.
+#[derive(Debug)]
+pub struct RegionReport {
+    /// Set if the file is smaller than the two fixed 4096-byte tables require. If set, `problems`
+    /// is always empty, since neither table can be read to find anything more specific.
+    pub file_too_small: Option<(usize, usize)>,
+    /// Every problem found while walking the posistion table and chunk headers, in table order.
+    pub problems: Vec<RegionProblem>,
+}
+
+impl RegionReport {
+    /// `true` if nothing was flagged at all.
+    pub fn is_healthy(&self) -> bool {
+        self.file_too_small.is_none() && self.problems.is_empty()
+    }
+}
+
+/// A single corruption finding, naming the posistion-table index of the offending chunk.
+#[derive(Debug)]
+pub struct RegionProblem {
+    pub chunk_index: usize,
+    pub kind: RegionProblemKind,
+}
+
+/// The kinds of corruption [`scan`] checks for.
+#[derive(Debug)]
+pub enum RegionProblemKind {
+    /// The posistion table's declared range for this chunk falls outside the file (or is too
+    /// short to even hold a chunk header).
+    OffsetOutOfBounds { offset: usize, end: usize, file_len: usize },
+    /// The chunk header's declared length is larger than the sector run the posistion table
+    /// allocated to it.
+    HeaderLengthExceedsAllocation { declared: usize, allocated: usize },
+    /// This chunk's byte range overlaps that of the chunk starting just before it in the file
+    /// (which may have a higher table index).
+    OverlappingRange { other_chunk_index: usize },
+    /// The chunk header's compression byte does not match a known compression scheme.
+    UnknownCompressionByte { byte: u8 },
+    /// A tag this crate expects every chunk to carry was missing. A chunk that fails to
+    /// decompress, or whose NBT fails to parse, is reported as missing `Level`.
+    MissingTag { tag: &'static str },
+}
+
+/// Scans a region's raw bytes for corruption, without assuming it parses cleanly as a
+/// [`super::RawRegion`] - so one damaged chunk does not stop the rest of the region from being
+/// inspected the way a `?` in [`super::RawRegion::from_file`] would. Set `check_nbt` to also
+/// decompress every chunk and confirm its `Level` compound carries `xPos` and `zPos`.
+pub fn scan(file: &[u8], schema: &AnvilSchema, check_nbt: bool) -> RegionReport {
+    if file.len() < schema.min_anvil_file_size {
+        return RegionReport {
+            file_too_small: Some((schema.min_anvil_file_size, file.len())),
+            problems: Vec::new(),
+        };
+    }
+
+    // The file length check above guarantees both fixed tables lie fully within `file`, so this
+    // cannot fail here even though `get_posistion_table` is fallible in general.
+    let positions = get_posistion_table(file, schema)
+        .expect("file is at least min_anvil_file_size bytes, checked above");
+    let mut problems = Vec::new();
+    // (start, end, chunk_index) of every chunk whose range could be read, for the overlap pass
+    let mut readable_ranges: Vec<(usize, usize, usize)> = Vec::new();
+
+    for (chunk_index, (pos, size)) in positions.iter().enumerate() {
+        if *pos == 0 {
+            continue;
+        }
+        let end = pos + size;
+        let chunk = match slice(file, *pos..end) {
+            Ok(chunk) if chunk.len() >= schema.chunk_starts_from => chunk,
+            _ => {
+                problems.push(RegionProblem {
+                    chunk_index,
+                    kind: RegionProblemKind::OffsetOutOfBounds { offset: *pos, end, file_len: file.len() },
+                });
+                continue;
+            }
+        };
+        readable_ranges.push((*pos, end, chunk_index));
+
+        let (compr_start, compr_end) = schema.chunk_header_compr_bytes;
+        let (size_start, size_end) = schema.chunk_header_size_bytes;
+        let compr_byte = match slice(chunk, compr_start..compr_end) {
+            Ok(bytes) => bytes[0],
+            Err(_) => {
+                problems.push(RegionProblem {
+                    chunk_index,
+                    kind: RegionProblemKind::OffsetOutOfBounds { offset: *pos, end, file_len: file.len() },
+                });
+                continue;
+            }
+        };
+        let declared_len = match slice(chunk, size_start..size_end) {
+            Ok(bytes) => make_usize_from_bytes(bytes) + size_end,
+            Err(_) => {
+                problems.push(RegionProblem {
+                    chunk_index,
+                    kind: RegionProblemKind::OffsetOutOfBounds { offset: *pos, end, file_len: file.len() },
+                });
+                continue;
+            }
+        };
+
+        // Compare against this chunk's own allocated length, not its absolute file offset - a
+        // declared body length can exceed the sector run it was given while still being smaller
+        // than `end`, which would otherwise sail past this check and panic on the slice below.
+        if declared_len > chunk.len() {
+            problems.push(RegionProblem {
+                chunk_index,
+                kind: RegionProblemKind::HeaderLengthExceedsAllocation { declared: declared_len, allocated: *size },
+            });
+            continue;
+        }
+
+        // `Custom` chunks are recognised as valid but not inspected further: this crate has no way
+        // to run an arbitrary codec, so there's no NBT to check without decompressing it first.
+        let (compression, is_custom) = match compr_byte & 0x7f {
+            1 => (Compression::GZip, false),
+            2 => (Compression::ZLib, false),
+            3 => (Compression::Uncompressed, false),
+            4 => (Compression::Lz4, false),
+            127 => (Compression::Custom(String::new()), true),
+            _ => {
+                problems.push(RegionProblem {
+                    chunk_index,
+                    kind: RegionProblemKind::UnknownCompressionByte { byte: compr_byte },
+                });
+                continue;
+            }
+        };
+
+        let external = compr_byte & 0x80 != 0;
+        if check_nbt && !external && !is_custom {
+            let data = match slice(chunk, schema.chunk_starts_from..declared_len) {
+                Ok(bytes) => bytes.to_vec(),
+                Err(_) => {
+                    problems.push(RegionProblem {
+                        chunk_index,
+                        kind: RegionProblemKind::HeaderLengthExceedsAllocation { declared: declared_len, allocated: *size },
+                    });
+                    continue;
+                }
+            };
+            let raw_chunk = RawChunk {
+                compression,
+                data,
+                timestamp: 0,
+                sector_offset: 0,
+                external,
+                region_x: 0,
+                region_z: 0,
+            };
+            let level = raw_chunk.decompress().ok()
+                .and_then(|bytes| Nbt::from_bytes(&bytes).ok())
+                .and_then(|nbt| nbt.get("Level").cloned());
+            match level {
+                Some(level) => {
+                    for tag in REQUIRED_LEVEL_TAGS {
+                        if level.get(tag).is_none() {
+                            problems.push(RegionProblem { chunk_index, kind: RegionProblemKind::MissingTag { tag } });
+                        }
+                    }
+                }
+                None => problems.push(RegionProblem { chunk_index, kind: RegionProblemKind::MissingTag { tag: "Level" } }),
+            }
+        }
+    }
+
+    readable_ranges.sort_by_key(|&(start, _, _)| start);
+    for window in readable_ranges.windows(2) {
+        let (_, prev_end, prev_index) = window[0];
+        let (next_start, _, next_index) = window[1];
+        if next_start < prev_end {
+            problems.push(RegionProblem {
+                chunk_index: next_index,
+                kind: RegionProblemKind::OverlappingRange { other_chunk_index: prev_index },
+            });
+        }
+    }
+
+    RegionReport { file_too_small: None, problems }
+}
+
+/// How [`repair`] should act on the problems a [`scan`] found.
+pub enum RepairMode {
+    /// Zero the posistion/timestamp table entries of every chunk [`scan`] flagged, so the game
+    /// treats them as never having been generated and regenerates them, leaving every other
+    /// chunk's bytes untouched.
+    DeleteCorruptChunks,
+}
+
+/// Repairs a region's raw bytes in-place according to `mode`, using [`scan`] to find the chunks
+/// that need action. Returns the repaired file bytes; the caller is responsible for writing them
+/// back out.
+pub fn repair(file: &[u8], schema: &AnvilSchema, mode: RepairMode) -> Vec<u8> {
+    let RepairMode::DeleteCorruptChunks = mode;
+    let report = scan(file, schema, true);
+    let mut repaired = file.to_vec();
+    if report.file_too_small.is_some() {
+        return repaired;
+    }
+    for problem in &report.problems {
+        let record_start = problem.chunk_index * schema.posistion_table_record_len;
+        for byte in &mut repaired[record_start..record_start + schema.posistion_table_record_len] {
+            *byte = 0;
+        }
+        let timestamp_start = schema.chunks_per_region * schema.posistion_table_record_len
+            + problem.chunk_index * 4;
+        for byte in &mut repaired[timestamp_start..timestamp_start + 4] {
+            *byte = 0;
+        }
+    }
+    repaired
+}
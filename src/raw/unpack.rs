@@ -1,105 +1,296 @@
-use super::{ 
-    AnvilSchema, 
+use std::ops::Range;
+
+use super::{
+    AnvilSchema,
     RawError,
     RawChunk,
     Compression
 };
 
-pub fn get_posistion_table(file: &[u8], schema: &AnvilSchema) -> Vec<(usize, usize)>
+/// Slices `buf[range]`, returning a descriptive `RawError` instead of panicking if `range` falls
+/// outside `buf` - the only way any of these parsers should ever encounter a truncated or
+/// otherwise malformed file.
+pub(super) fn slice(buf: &[u8], range: Range<usize>) -> Result<&[u8], RawError> {
+    buf.get(range.clone()).ok_or_else(|| RawError::throw_slice_err(range.start, range.end, buf.len()))
+}
+
+pub fn get_posistion_table(file: &[u8], schema: &AnvilSchema) -> Result<Vec<(usize, usize)>, RawError>
 {
     let mut output_vec: Vec<(usize, usize)> = Vec::new();
     for iter in 0..schema.chunks_per_region {
-        let record = get_pos_record(file, iter, schema);
+        let record = get_pos_record(file, iter, schema)?;
         output_vec.push(record);
     }
-    output_vec
+    Ok(output_vec)
 }
 
-fn get_pos_record(file: &[u8], rec_no: usize, schema: &AnvilSchema) -> (usize, usize) {
-    let offset = (rec_no * schema.posistion_table_record_len) as usize;
+fn get_pos_record(file: &[u8], rec_no: usize, schema: &AnvilSchema) -> Result<(usize, usize), RawError> {
+    let offset = rec_no * schema.posistion_table_record_len ;
 
     let (pos_data_start, pos_data_end) = schema.pos_table_start_bytes;
     let (size_data_start, size_data_end) = schema.pos_table_size_bytes;
 
-    let pos_data_bytes = &file[pos_data_start + offset..pos_data_end + offset];
-    let size_data_bytes = &file[size_data_start + offset..size_data_end + offset];
+    let pos_data_bytes = slice(file, pos_data_start + offset..pos_data_end + offset)?;
+    let size_data_bytes = slice(file, size_data_start + offset..size_data_end + offset)?;
     let pos_index = make_usize_from_bytes(pos_data_bytes) * schema.pos_multiplier;
     let size_index = make_usize_from_bytes(size_data_bytes) * schema.size_multiplier;
-    (pos_index, size_index)
+    Ok((pos_index, size_index))
+}
+
+/// The number of reserved 4096-byte sectors (the posistion table, then the timestamp table) at
+/// the start of every region file, which no chunk may occupy.
+const RESERVED_SECTOR_COUNT: usize = 2;
+
+/// Strictly validates a region's posistion table: that no chunk's declared range falls inside the
+/// reserved posistion/timestamp tables, that no two chunks' ranges overlap, and that - once
+/// sorted by offset - every chunk declares a strictly greater offset than the one before it.
+/// Unlike [`super::report::scan`], which collects every problem it finds into a structured report
+/// so a partially damaged file can still be inspected, this stops at the first problem, making it
+/// useful as a fail-fast gate in front of code that expects a well-formed file.
+pub fn validate_layout(file: &[u8], schema: &AnvilSchema) -> Result<(), RawError> {
+    let positions = get_posistion_table(file, schema)?;
+    let reserved_end = RESERVED_SECTOR_COUNT * schema.size_multiplier;
+
+    let mut records: Vec<(usize, usize, usize)> = positions
+        .iter()
+        .enumerate()
+        .filter(|(_, (pos, _))| *pos != 0)
+        .map(|(index, (pos, size))| (*pos, pos + size, index))
+        .collect();
+    records.sort_by_key(|&(start, _, _)| start);
+
+    for &(start, end, index) in &records {
+        if start < reserved_end {
+            return Err(RawError::throw_reserved_sector_err(index, start, end));
+        }
+    }
+    for window in records.windows(2) {
+        let (prev_start, prev_end, prev_index) = window[0];
+        let (next_start, next_end, next_index) = window[1];
+        if next_start == prev_start {
+            return Err(RawError::throw_non_incremental_offset_err(
+                prev_index, (prev_start, prev_end), next_index, (next_start, next_end),
+            ));
+        }
+        if next_start < prev_end {
+            return Err(RawError::throw_chunk_overlap_err(
+                prev_index, (prev_start, prev_end), next_index, (next_start, next_end),
+            ));
+        }
+    }
+    Ok(())
 }
 
-pub fn get_timestamp_table(file: &[u8], schema: &AnvilSchema) -> Vec<u32> {
+/// The length in bytes of a single record in a `.crc` sidecar, mirroring `pack::CRC_RECORD_LEN`.
+const CRC_RECORD_LEN: usize = 4;
+
+/// Reads back a single chunk's CRC32 from a `.crc` sidecar produced by
+/// `pack::encode_checksum_sidecar`.
+fn decode_checksum(sidecar: &[u8], chunk_index: usize) -> Result<u32, RawError> {
+    let start = chunk_index * CRC_RECORD_LEN;
+    let end = start + CRC_RECORD_LEN;
+    let bytes = slice(sidecar, start..end)?;
+    Ok(u32::from_be_bytes(bytes.try_into().expect("slice is exactly CRC_RECORD_LEN bytes long")))
+}
+
+/// Recomputes each table slot's CRC32 straight from a region file's raw bytes and compares it
+/// against `sidecar`, a `.crc` file previously produced alongside it (see
+/// `pack::encode_checksum_sidecar`). Confirms a region survived a copy or transfer intact, since
+/// Anvil itself has no per-chunk checksum of its own.
+///
+/// # Errors
+///
+/// Returns the first `RawError::ChunkChecksumErr` found, carrying the offending chunk's table
+/// index and its expected/actual CRC32. Returns `RawError::UnpackSliceErr` instead if `sidecar`
+/// doesn't have a record for every slot `file`'s posistion table declares, e.g. a stale sidecar
+/// left over from before the region was repacked.
+pub fn verify_checksums(file: &[u8], sidecar: &[u8], schema: &AnvilSchema) -> Result<(), RawError> {
+    let positions = get_posistion_table(file, schema)?;
+    for (index, (pos, size)) in positions.iter().enumerate() {
+        if *pos == 0 {
+            continue;
+        }
+        let end = pos + size;
+        let chunk_bytes = slice(file, *pos..end)?;
+        let actual = crc32fast::hash(chunk_bytes);
+        let expected = decode_checksum(sidecar, index)?;
+        if actual != expected {
+            return Err(RawError::throw_checksum_err(index, expected, actual));
+        }
+    }
+    Ok(())
+}
+
+pub fn get_timestamp_table(file: &[u8], schema: &AnvilSchema) -> Result<Vec<u32>, RawError> {
     let mut output = Vec::new();
     let timestamp_table_start = schema.chunks_per_region * schema.posistion_table_record_len;
     for iter in 0..schema.chunks_per_region {
-        let offset = (timestamp_table_start + iter * 4) as usize;
-        let timestamp_bytes = &file[offset..offset + 4];
+        let offset = timestamp_table_start + iter * 4 ;
+        let timestamp_bytes = slice(file, offset..offset + 4)?;
         let timestamp = make_u32_from_bytes(timestamp_bytes);
         output.push(timestamp);
     }
-    output
-} 
+    Ok(output)
+}
 
+/// Extracts every chunk named in `headers`, in table order. On a build with the `rayon` feature
+/// enabled, the table is walked across a thread pool (`rayon`'s indexed parallel iterators keep
+/// `collect` in source order, so the returned `Vec` lines up with `headers` exactly as it would
+/// single-threaded); without the feature, it falls back to a plain sequential walk.
+#[cfg(feature = "rayon")]
 pub fn get_chunks(file: &[u8], headers: &[(usize, usize)], timestamp_table: &[u32], schema: &AnvilSchema) -> Result<Vec<RawChunk>, RawError> {
-    let mut chunks: Vec<RawChunk> = Vec::new();
+    use rayon::prelude::*;
+    headers
+        .par_iter()
+        .enumerate()
+        .map(|(iter, (pos, size))| get_chunk_or_blank(file, iter, *pos, *size, timestamp_table[iter], schema))
+        .collect()
+}
+
+/// See the `rayon`-enabled [`get_chunks`] above; this is the sequential fallback used when that
+/// feature is off.
+#[cfg(not(feature = "rayon"))]
+pub fn get_chunks(file: &[u8], headers: &[(usize, usize)], timestamp_table: &[u32], schema: &AnvilSchema) -> Result<Vec<RawChunk>, RawError> {
+    headers
+        .iter()
+        .enumerate()
+        .map(|(iter, (pos, size))| get_chunk_or_blank(file, iter, *pos, *size, timestamp_table[iter], schema))
+        .collect()
+}
+
+/// Like [`get_chunks`], but never stops at the first bad chunk: every slot in `headers` is parsed
+/// independently, and any that fail are swapped for a blank `Compression::None` placeholder (as
+/// if their posistion-table record had been zeroed) instead of aborting the whole region. Their
+/// errors are collected in table order alongside the otherwise-usable `Vec<RawChunk>`.
+///
+/// If `options.drop_corrupt_chunks` is set, a dropped chunk's timestamp is zeroed along with its
+/// data, matching [`super::repair`]'s `DeleteCorruptChunks` mode; otherwise the original
+/// timestamp is kept so the caller can still tell when the unreadable chunk was last written.
+pub fn get_chunks_recovering(
+    file: &[u8],
+    headers: &[(usize, usize)],
+    timestamp_table: &[u32],
+    schema: &AnvilSchema,
+    options: &super::RecoveryOptions,
+) -> (Vec<RawChunk>, Vec<RawError>) {
+    let mut chunks = Vec::with_capacity(headers.len());
+    let mut errors = Vec::new();
     for (iter, (pos, size)) in headers.iter().enumerate() {
-        let timestamp = timestamp_table[iter];
-        if *pos != 0 {
-            let end_pos = pos + size;
-            let chunk = get_chunk(&file, *pos, end_pos, iter, timestamp, &schema)?;
-            chunks.push(chunk);
-        } else {
-            chunks.push((Compression::None, Vec::new(), timestamp));
+        match get_chunk_or_blank(file, iter, *pos, *size, timestamp_table[iter], schema) {
+            Ok(chunk) => chunks.push(chunk),
+            Err(err) => {
+                errors.push(err);
+                let (region_x, region_z) = chunk_coords(iter, schema);
+                let timestamp = if options.drop_corrupt_chunks { 0 } else { timestamp_table[iter] };
+                chunks.push(RawChunk {
+                    compression: Compression::None,
+                    data: Vec::new(),
+                    timestamp,
+                    sector_offset: 0,
+                    external: false,
+                    region_x,
+                    region_z,
+                });
+            }
         }
     }
-    Ok(chunks)
+    (chunks, errors)
+}
+
+/// Extracts a single posistion-table entry: a real chunk if `pos` is non-zero, or a blank
+/// `Compression::None` placeholder if the slot has never been written to.
+fn get_chunk_or_blank(file: &[u8], iter: usize, pos: usize, size: usize, timestamp: u32, schema: &AnvilSchema) -> Result<RawChunk, RawError> {
+    if pos != 0 {
+        let end_pos = pos + size;
+        let sector_offset = pos / schema.pos_multiplier;
+        get_chunk(file, pos, end_pos, iter, timestamp, sector_offset, schema)
+    } else {
+        let (region_x, region_z) = chunk_coords(iter, schema);
+        Ok(RawChunk {
+            compression: Compression::None,
+            data: Vec::new(),
+            timestamp,
+            sector_offset: 0,
+            external: false,
+            region_x,
+            region_z,
+        })
+    }
+}
+
+/// A chunk's region-relative `(x, z)` coordinates, derived from its index in the posistion table
+/// assuming a square region (true of every real Anvil region, which is always 32x32 chunks).
+fn chunk_coords(chunk_index: usize, schema: &AnvilSchema) -> (u8, u8) {
+    let width = (schema.chunks_per_region as f64).sqrt() as usize;
+    ((chunk_index % width) as u8, (chunk_index / width) as u8)
 }
 
-fn get_chunk(file: &[u8], start: usize, end: usize, chunk_index: usize, timestamp: u32, schema: &AnvilSchema) -> Result<RawChunk, RawError> {
+fn get_chunk(file: &[u8], start: usize, end: usize, chunk_index: usize, timestamp: u32, sector_offset: usize, schema: &AnvilSchema) -> Result<RawChunk, RawError> {
     if file.len() < end {
         return Err(RawError::throw_chunk_pos_err(chunk_index, file.len(), end))
     }
-    let chunk = &file[start..end];
-    let (size, compression) = parse_chunk_header(&chunk, &schema);
-    let output_vec = if size > end {
-        return Err(RawError::throw_chunk_header_err(chunk_index, end, size)); 
+    let chunk = slice(file, start..end)?;
+    let (size, compression, external, data_start) = parse_chunk_header(chunk, schema)?;
+    // Compare against this chunk's own allocated length, not its absolute file offset - `size` is
+    // relative to `chunk`, so bounding it by `end` let a declared body length sail past this check
+    // and panic (or, post-fix, hit a generic slice error) on the read below instead of surfacing
+    // the header as invalid.
+    let output_vec = if size > chunk.len() {
+        return Err(RawError::throw_chunk_header_err(chunk_index, chunk.len(), size));
     } else {
-        chunk[schema.chunk_starts_from..size].to_vec()
+        slice(chunk, data_start..size)?.to_vec()
     };
-    Ok((compression, output_vec, timestamp))
+    let (region_x, region_z) = chunk_coords(chunk_index, schema);
+    Ok(RawChunk { compression, data: output_vec, timestamp, sector_offset, external, region_x, region_z })
 }
 
-// Parses the first few bytes of a chunk
-fn parse_chunk_header(chunk: &[u8], schema: &AnvilSchema) -> (usize, Compression) 
+/// Parses the first few bytes of a chunk, returning its declared end offset (relative to `chunk`),
+/// its compression scheme, whether it's stored externally, and the offset (also relative to
+/// `chunk`) its compressed data starts at. That last offset is only ever `schema.chunk_starts_from`
+/// for a built-in scheme; `Compression::Custom` carries a namespaced key after the compression
+/// byte, which pushes the data start back further.
+fn parse_chunk_header(chunk: &[u8], schema: &AnvilSchema) -> Result<(usize, Compression, bool, usize), RawError>
 {
     let (compression_byte_start, compression_byte_end) = schema.chunk_header_compr_bytes;
     let (size_bytes_start, size_bytes_end) = schema.chunk_header_size_bytes;
 
-    let compression_bytes = &chunk[compression_byte_start..compression_byte_end];
-    let size_bytes = &chunk[size_bytes_start..size_bytes_end];
+    let compression_bytes = slice(chunk, compression_byte_start..compression_byte_end)?;
+    let size_bytes = slice(chunk, size_bytes_start..size_bytes_end)?;
 
-    let size = make_usize_from_bytes(&size_bytes) + schema.chunk_starts_from;
-    let compression = match make_usize_from_bytes(compression_bytes) {
-        1 => Compression::GZip,
-        2 => Compression::ZLib,
-        _ => Compression::None
+    let declared_len = make_usize_from_bytes(size_bytes);
+    let raw_scheme = make_usize_from_bytes(compression_bytes);
+    let external = raw_scheme & 0x80 != 0;
+    let (compression, data_start) = match raw_scheme & 0x7f {
+        1 => (Compression::GZip, compression_byte_end),
+        2 => (Compression::ZLib, compression_byte_end),
+        3 => (Compression::Uncompressed, compression_byte_end),
+        4 => (Compression::Lz4, compression_byte_end),
+        127 => {
+            let key_len_bytes = slice(chunk, compression_byte_end..compression_byte_end + 2)?;
+            let key_len = ((key_len_bytes[0] as usize) << 8) + key_len_bytes[1] as usize;
+            let key_start = compression_byte_end + 2;
+            let key_bytes = slice(chunk, key_start..key_start + key_len)?;
+            (Compression::Custom(String::from_utf8_lossy(key_bytes).into_owned()), key_start + key_len)
+        }
+        _ => (Compression::None, compression_byte_end),
     };
-    (size, compression)
+    let size = size_bytes_end + declared_len;
+    Ok((size, compression, external, data_start))
 }
 
-// Helper funtion to turn arrays of bytes read from files into full numbers 
-fn make_usize_from_bytes(bytes: &[u8]) -> usize {
-    let mut output: usize = 0; 
-    for (iter, val) in bytes.into_iter().rev().enumerate() {
-        output += (*val as usize) << (iter * 8); 
+// Helper funtion to turn arrays of bytes read from files into full numbers
+pub(super) fn make_usize_from_bytes(bytes: &[u8]) -> usize {
+    let mut output: usize = 0;
+    for (iter, val) in bytes.iter().rev().enumerate() {
+        output += (*val as usize) << (iter * 8);
     }
     output
 }
-// Helper funtion to turn arrays of bytes read from files into full numbers 
+// Helper funtion to turn arrays of bytes read from files into full numbers
 fn make_u32_from_bytes(bytes: &[u8]) -> u32 {
-    let mut output: u32 = 0; 
-    for (iter, val) in bytes.into_iter().rev().enumerate() {
-        output += (*val as u32) << (iter * 8); 
+    let mut output: u32 = 0;
+    for (iter, val) in bytes.iter().rev().enumerate() {
+        output += (*val as u32) << (iter * 8);
     }
     output
 }
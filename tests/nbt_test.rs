@@ -0,0 +1,60 @@
+use anvil_lib::nbt::Nbt;
+
+fn tag_name(id: u8, name: &str, bytes: &mut Vec<u8>) {
+    bytes.push(id);
+    bytes.extend((name.len() as u16).to_be_bytes());
+    bytes.extend(name.as_bytes());
+}
+
+/// Hand-builds a root TAG_Compound("") shaped like a real chunk's NBT body:
+///
+/// ```text
+/// TAG_Compound("")
+///   TAG_Compound("Level")
+///     TAG_Int("xPos"): 5
+///     TAG_List("Sections"): [TAG_Compound { TAG_Byte("Y"): 0 }]
+/// ```
+fn level_compound_bytes() -> Vec<u8> {
+    let mut bytes = Vec::new();
+    tag_name(10, "", &mut bytes); // root TAG_Compound
+
+    tag_name(10, "Level", &mut bytes); // Level TAG_Compound
+
+    tag_name(3, "xPos", &mut bytes); // xPos TAG_Int
+    bytes.extend(5i32.to_be_bytes());
+
+    tag_name(9, "Sections", &mut bytes); // Sections TAG_List
+    bytes.push(10); // element type: TAG_Compound
+    bytes.extend(1i32.to_be_bytes()); // one element
+    tag_name(1, "Y", &mut bytes); // section's Y TAG_Byte
+    bytes.push(0);
+    bytes.push(0); // end the section compound
+
+    bytes.push(0); // end the Level compound
+    bytes.push(0); // end the root compound
+    bytes
+}
+
+#[test]
+fn from_bytes_parses_a_real_shaped_compound_and_path_walks_into_it() {
+    let bytes = level_compound_bytes();
+    let root = Nbt::from_bytes(&bytes).expect("a well-formed compound should parse");
+
+    assert_eq!(root.path("Level/xPos"), Some(&Nbt::Int(5)));
+    assert_eq!(
+        root.path("Level/Sections"),
+        Some(&Nbt::List(vec![Nbt::Compound(vec![("Y".to_string(), Nbt::Byte(0))])])),
+    );
+    assert_eq!(root.path("Level/Missing"), None);
+    assert_eq!(root.path("Missing/xPos"), None);
+}
+
+#[test]
+fn a_malformed_byte_array_length_returns_an_error_instead_of_panicking() {
+    // A root TAG_Byte_Array("") whose declared element count is -1 (0xFFFFFFFF), which would
+    // otherwise cast to a huge `usize` and panic `Vec::with_capacity` instead of failing cleanly.
+    let mut bytes = vec![7u8, 0, 0];
+    bytes.extend(0xFFFFFFFFu32.to_be_bytes());
+
+    assert!(Nbt::from_bytes(&bytes).is_err());
+}
@@ -1,17 +1,400 @@
-use anvil_lib::raw::get_region_raw;
-use std::fs;
+use anvil_lib::raw::{
+    scan, repair, AnvilSchema, Compression, RawChunk, RawError, RawRegion, RecoveryOptions, RepairMode,
+    RegionProblemKind,
+};
 
+fn blank_region(schema: &AnvilSchema) -> RawRegion {
+    let chunks = (0..schema.chunks_per_region)
+        .map(|_| RawChunk {
+            compression: Compression::None,
+            data: Vec::new(),
+            timestamp: 0,
+            sector_offset: 0,
+            external: false,
+            region_x: 0,
+            region_z: 0,
+        })
+        .collect();
+    RawRegion { chunks }
+}
+
+#[test]
+fn round_trips_a_single_chunk_through_to_file_and_from_file() {
+    let schema = AnvilSchema::default();
+    let mut region = blank_region(&schema);
+
+    let nbt_payload = b"hello anvil".to_vec();
+    let mut chunk = RawChunk::from_decompressed(&nbt_payload, Compression::ZLib);
+    chunk.timestamp = 1234;
+    region.chunks[0] = chunk;
+
+    let (file, external) = region.to_file(&schema).expect("a fully populated region should pack");
+    assert!(external.is_empty());
+
+    let round_tripped = RawRegion::from_file(&file, &schema).expect("a region this crate packed should unpack");
+    assert_eq!(round_tripped.chunks[0].timestamp, 1234);
+    assert_eq!(round_tripped.chunks[0].decompress().unwrap(), nbt_payload);
+}
+
+#[test]
+fn scan_reports_a_header_length_exceeding_its_allocation_instead_of_panicking() {
+    let schema = AnvilSchema::default();
+
+    // Posistion table record 0: chunk at sector 2, spanning 1 sector (4096 bytes).
+    let mut file = vec![0u8; 2 * schema.pos_multiplier + schema.size_multiplier];
+    file[0..3].copy_from_slice(&[0, 0, 2]);
+    file[3] = 1;
+
+    // That chunk's header declares a body length (4995, plus the 4-byte length field itself) of
+    // 4999 bytes - larger than the single 4096-byte sector the posistion table allocated to it.
+    let chunk_start = 2 * schema.pos_multiplier;
+    file[chunk_start..chunk_start + 4].copy_from_slice(&4995u32.to_be_bytes());
+    file[chunk_start + 4] = 2; // ZLib
+
+    let report = scan(&file, &schema, false);
+
+    assert_eq!(report.problems.len(), 1);
+    match report.problems[0].kind {
+        RegionProblemKind::HeaderLengthExceedsAllocation { declared, allocated } => {
+            assert_eq!(declared, 4999);
+            assert_eq!(allocated, schema.size_multiplier);
+        }
+        ref other => panic!("expected HeaderLengthExceedsAllocation, got {other:?}"),
+    }
+}
+
+#[test]
+fn rebuilding_one_chunk_does_not_shift_the_chunks_before_it() {
+    let schema = AnvilSchema::default();
+    let mut region = blank_region(&schema);
+
+    for i in 0..3 {
+        let mut chunk = RawChunk::from_decompressed(&[b'a' + i as u8; 16], Compression::ZLib);
+        chunk.timestamp = 1000 + i as u32;
+        region.chunks[i] = chunk;
+    }
+
+    let (file, _) = region.to_file(&schema).expect("three small chunks should pack inline");
+    let mut region = RawRegion::from_file(&file, &schema).expect("should unpack what this crate packed");
+    let sector_offset_before = region.chunks[0].sector_offset;
+    assert_ne!(sector_offset_before, 0, "chunk 0 should have landed on a real sector");
+
+    // Replace only chunk 1 - `from_decompressed` always resets `sector_offset` back to 0, which
+    // is exactly what used to make every other chunk cascade-shift.
+    let mut replacement = RawChunk::from_decompressed(b"replaced", Compression::ZLib);
+    replacement.timestamp = 9999;
+    region.chunks[1] = replacement;
+
+    let (file, _) = region.to_file(&schema).expect("still three small chunks");
+    let region = RawRegion::from_file(&file, &schema).expect("should unpack what this crate packed");
+
+    assert_eq!(region.chunks[0].sector_offset, sector_offset_before);
+    assert_eq!(region.chunks[0].timestamp, 1000);
+    assert_eq!(region.chunks[1].timestamp, 9999);
+}
+
+#[test]
+fn from_file_returns_a_chunk_header_error_instead_of_panicking_on_an_overlong_header() {
+    let schema = AnvilSchema::default();
+
+    // Posistion table record 0: chunk at sector 2, spanning 1 sector (4096 bytes).
+    let mut file = vec![0u8; 2 * schema.pos_multiplier + schema.size_multiplier];
+    file[0..3].copy_from_slice(&[0, 0, 2]);
+    file[3] = 1;
+
+    // The header declares a body so long that reading it would run past the end of this chunk's
+    // single allocated sector, even though the declared length (9999 bytes with its length
+    // field) is still smaller than the chunk's end offset in the file (12288).
+    let chunk_start = 2 * schema.pos_multiplier;
+    file[chunk_start..chunk_start + 4].copy_from_slice(&9995u32.to_be_bytes());
+    file[chunk_start + 4] = 3; // Uncompressed
+
+    match RawRegion::from_file(&file, &schema) {
+        Err(RawError::UnpackChunkHeaderErr(_)) => {}
+        Err(other) => panic!("expected RawError::UnpackChunkHeaderErr, got {other:?}"),
+        Ok(_) => panic!("expected this overlong header to be rejected"),
+    }
+}
+
+#[test]
+fn lz4_and_uncompressed_chunks_round_trip_through_to_file_and_from_file() {
+    let schema = AnvilSchema::default();
+    let mut region = blank_region(&schema);
+
+    let lz4_payload = b"lz4 chunk payload".to_vec();
+    region.chunks[0] = RawChunk::from_decompressed(&lz4_payload, Compression::Lz4);
+    let uncompressed_payload = b"uncompressed chunk payload".to_vec();
+    region.chunks[1] = RawChunk::from_decompressed(&uncompressed_payload, Compression::Uncompressed);
+
+    let (file, external) = region.to_file(&schema).expect("two small chunks should pack inline");
+    assert!(external.is_empty());
+
+    let round_tripped = RawRegion::from_file(&file, &schema).expect("should unpack what this crate packed");
+    assert_eq!(round_tripped.chunks[0].compression, Compression::Lz4);
+    assert_eq!(round_tripped.chunks[0].decompress().unwrap(), lz4_payload);
+    assert_eq!(round_tripped.chunks[1].compression, Compression::Uncompressed);
+    assert_eq!(round_tripped.chunks[1].decompress().unwrap(), uncompressed_payload);
+}
 
 #[test]
-fn pack_test_ok() {
-    let file = fs::read("data/test.bin").expect("Can't open file.");
-    match get_region_raw(&file) {
-        Ok(val) => { 
-            let s = &val[0].0; // 78 9c ed 
-            for i in 0..10 {
-                println!("{}", s[i]);
-            }
-        }, 
-        Err(error) => panic!("{:?}", error) 
+fn get_chunks_returns_every_slot_lined_up_with_its_table_index() {
+    let schema = AnvilSchema::default();
+    let mut region = blank_region(&schema);
+
+    // Scattered, non-adjacent table slots, each with a distinct timestamp - if extraction (single
+    // threaded, or parallel under the `rayon` feature) didn't keep results lined up with their
+    // table index, this would catch chunks ending up attributed to the wrong slot.
+    let populated_indices = [0usize, 7, 42, 500, 1023];
+    for (n, &index) in populated_indices.iter().enumerate() {
+        let mut chunk = RawChunk::from_decompressed(format!("chunk {index}").as_bytes(), Compression::ZLib);
+        chunk.timestamp = 1000 + n as u32;
+        region.chunks[index] = chunk;
+    }
+
+    let (file, _) = region.to_file(&schema).expect("a sparsely populated region should still pack");
+    let round_tripped = RawRegion::from_file(&file, &schema).expect("should unpack what this crate packed");
+
+    for (n, &index) in populated_indices.iter().enumerate() {
+        assert_eq!(round_tripped.chunks[index].timestamp, 1000 + n as u32);
+        assert_eq!(
+            round_tripped.chunks[index].decompress().unwrap(),
+            format!("chunk {index}").into_bytes(),
+        );
     }
+    for index in 0..schema.chunks_per_region {
+        if !populated_indices.contains(&index) {
+            assert!(round_tripped.chunks[index].data.is_empty());
+        }
+    }
+}
+
+#[test]
+fn an_oversized_chunk_is_split_out_to_an_external_sidecar_instead_of_packed_inline() {
+    let schema = AnvilSchema::default();
+    let mut region = blank_region(&schema);
+
+    // Bigger than 255 sectors (~1 MiB) once headered and padded, so `to_file` has to split it out.
+    let big_payload = vec![0xABu8; 1_100_000];
+    region.chunks[0] = RawChunk::from_decompressed(&big_payload, Compression::Uncompressed);
+
+    let (file, external) = region.to_file(&schema).expect("an oversized chunk should still pack");
+
+    assert_eq!(external.len(), 1);
+    assert_eq!(external[0].region_x, 0);
+    assert_eq!(external[0].region_z, 0);
+    assert_eq!(external[0].data, big_payload);
+
+    let round_tripped = RawRegion::from_file(&file, &schema).expect("should unpack what this crate packed");
+    assert!(round_tripped.chunks[0].external);
+}
+
+#[test]
+fn a_custom_compression_scheme_round_trips_its_namespaced_key_through_the_header() {
+    let schema = AnvilSchema::default();
+    let mut region = blank_region(&schema);
+
+    // `Custom` has no real codec behind it, so the caller is expected to have already compressed
+    // the data - `from_decompressed` just passes it through unchanged.
+    let already_compressed = b"opaque mod-specific codec output".to_vec();
+    region.chunks[0] = RawChunk::from_decompressed(&already_compressed, Compression::Custom("modid:super_codec".to_string()));
+
+    let (file, external) = region.to_file(&schema).expect("a small custom-compressed chunk should pack inline");
+    assert!(external.is_empty());
+
+    let round_tripped = RawRegion::from_file(&file, &schema).expect("should unpack what this crate packed");
+    assert_eq!(round_tripped.chunks[0].compression, Compression::Custom("modid:super_codec".to_string()));
+    assert_eq!(round_tripped.chunks[0].decompress().unwrap(), already_compressed);
+}
+
+#[test]
+fn from_file_recovering_salvages_a_region_with_one_corrupt_chunk() {
+    let schema = AnvilSchema::default();
+    let mut region = blank_region(&schema);
+    region.chunks[0] = RawChunk::from_decompressed(b"good chunk 0", Compression::ZLib);
+    region.chunks[1] = RawChunk::from_decompressed(b"good chunk 1", Compression::ZLib);
+
+    let (mut file, _) = region.to_file(&schema).expect("two small chunks should pack inline");
+    let packed = RawRegion::from_file(&file, &schema).expect("should unpack what this crate packed");
+
+    // Corrupt chunk 1's header so it can no longer be parsed, leaving chunk 0 untouched.
+    let corrupt_start = packed.chunks[1].sector_offset * schema.pos_multiplier;
+    file[corrupt_start..corrupt_start + 4].copy_from_slice(&u32::MAX.to_be_bytes());
+
+    let (recovered, errors) = RawRegion::from_file_recovering(&file, &schema, RecoveryOptions::default());
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(recovered.chunks[0].decompress().unwrap(), b"good chunk 0");
+    assert!(recovered.chunks[1].data.is_empty());
+    assert_eq!(recovered.chunks[1].timestamp, 0);
+}
+
+#[test]
+fn validate_layout_catches_two_chunks_whose_sectors_overlap() {
+    let schema = AnvilSchema::default();
+
+    // Record 0: sectors 2-3 (2 sectors). Record 1: sectors 3-3 (1 sector) - overlapping record 0's
+    // second sector.
+    let mut file = vec![0u8; 5 * schema.pos_multiplier];
+    file[0..4].copy_from_slice(&[0, 0, 2, 2]);
+    file[4..8].copy_from_slice(&[0, 0, 3, 1]);
+
+    match RawRegion::validate_layout(&file, &schema) {
+        Err(RawError::ChunkOverlapErr(_)) => {}
+        Err(other) => panic!("expected RawError::ChunkOverlapErr, got {other:?}"),
+        Ok(()) => panic!("expected the overlapping records to be rejected"),
+    }
+}
+
+#[test]
+fn verify_checksums_accepts_an_intact_region_and_rejects_a_tampered_one() {
+    let schema = AnvilSchema::default();
+    let mut region = blank_region(&schema);
+    region.chunks[0] = RawChunk::from_decompressed(b"checksummed chunk", Compression::ZLib);
+
+    let (mut file, external, sidecar) = region
+        .to_file_with_checksums(&schema)
+        .expect("a small chunk should pack inline");
+    assert!(external.is_empty());
+
+    RawRegion::verify_checksums(&file, &sidecar, &schema).expect("an untampered region should verify");
+
+    // Flip a byte inside the chunk's packed body, simulating corruption picked up in transit.
+    let tamper_at = schema.min_anvil_file_size;
+    file[tamper_at] ^= 0xFF;
+
+    match RawRegion::verify_checksums(&file, &sidecar, &schema) {
+        Err(RawError::ChunkChecksumErr(_)) => {}
+        other => panic!("expected RawError::ChunkChecksumErr, got {other:?}"),
+    }
+}
+
+/// Writes a posistion-table record: sector offset (3 bytes) and sector count (1 byte).
+fn write_pos_record(file: &mut [u8], schema: &AnvilSchema, chunk_index: usize, sector_offset: u32, sector_count: u8) {
+    let record_start = chunk_index * schema.posistion_table_record_len;
+    let offset_bytes = sector_offset.to_be_bytes();
+    file[record_start..record_start + 3].copy_from_slice(&offset_bytes[1..4]);
+    file[record_start + 3] = sector_count;
+}
+
+#[test]
+fn scan_reports_an_offset_that_falls_outside_the_file() {
+    let schema = AnvilSchema::default();
+    let mut file = vec![0u8; schema.min_anvil_file_size];
+    // Declares a chunk far past the end of this (minimally-sized) file.
+    write_pos_record(&mut file, &schema, 0, 9999, 1);
+
+    let report = scan(&file, &schema, false);
+
+    assert_eq!(report.problems.len(), 1);
+    match report.problems[0].kind {
+        RegionProblemKind::OffsetOutOfBounds { .. } => {}
+        ref other => panic!("expected OffsetOutOfBounds, got {other:?}"),
+    }
+}
+
+#[test]
+fn scan_reports_an_unrecognised_compression_byte() {
+    let schema = AnvilSchema::default();
+    let mut file = vec![0u8; 3 * schema.pos_multiplier];
+    write_pos_record(&mut file, &schema, 0, 2, 1);
+
+    // An empty body (declared length 0) under a compression byte that matches no known scheme.
+    let chunk_start = 2 * schema.pos_multiplier;
+    file[chunk_start + 4] = 200;
+
+    let report = scan(&file, &schema, false);
+
+    assert_eq!(report.problems.len(), 1);
+    match report.problems[0].kind {
+        RegionProblemKind::UnknownCompressionByte { byte } => assert_eq!(byte, 200),
+        ref other => panic!("expected UnknownCompressionByte, got {other:?}"),
+    }
+}
+
+#[test]
+fn scan_reports_two_chunks_whose_byte_ranges_overlap() {
+    let schema = AnvilSchema::default();
+    let mut file = vec![0u8; 5 * schema.pos_multiplier];
+
+    // Chunk 0: sectors 2-3 (2 sectors). Chunk 1: sectors 3-3 (1 sector) - overlapping chunk 0's
+    // second sector. Both carry a valid, empty ZLib header so only the overlap itself is flagged.
+    write_pos_record(&mut file, &schema, 0, 2, 2);
+    write_pos_record(&mut file, &schema, 1, 3, 1);
+    file[2 * schema.pos_multiplier + 4] = 2; // ZLib
+    file[3 * schema.pos_multiplier + 4] = 2; // ZLib
+
+    let report = scan(&file, &schema, false);
+
+    assert_eq!(report.problems.len(), 1);
+    match report.problems[0].kind {
+        RegionProblemKind::OverlappingRange { other_chunk_index } => assert_eq!(other_chunk_index, 0),
+        ref other => panic!("expected OverlappingRange, got {other:?}"),
+    }
+}
+
+#[test]
+fn scan_reports_a_decompressed_chunk_missing_its_level_tags() {
+    let schema = AnvilSchema::default();
+    let mut region = blank_region(&schema);
+
+    // A root TAG_Compound("") containing a "Level" TAG_Compound with an xPos but no zPos.
+    let mut nbt = vec![10u8, 0, 0]; // TAG_Compound, name ""
+    nbt.extend([10u8, 0, 5]);
+    nbt.extend(b"Level"); // TAG_Compound "Level"
+    nbt.extend([3u8, 0, 4]);
+    nbt.extend(b"xPos");
+    nbt.extend(5i32.to_be_bytes()); // TAG_Int "xPos": 5
+    nbt.push(0); // end Level
+    nbt.push(0); // end root
+
+    region.chunks[0] = RawChunk::from_decompressed(&nbt, Compression::ZLib);
+
+    let (file, _) = region.to_file(&schema).expect("a small chunk should pack inline");
+    let report = scan(&file, &schema, true);
+
+    assert_eq!(report.problems.len(), 1);
+    match report.problems[0].kind {
+        RegionProblemKind::MissingTag { tag } => assert_eq!(tag, "zPos"),
+        ref other => panic!("expected MissingTag, got {other:?}"),
+    }
+}
+
+/// A root TAG_Compound("") with a "Level" TAG_Compound carrying both required tags, matching what
+/// `scan`'s `check_nbt` pass expects of a healthy chunk.
+fn healthy_level_nbt() -> Vec<u8> {
+    let mut nbt = vec![10u8, 0, 0]; // TAG_Compound, name ""
+    nbt.extend([10u8, 0, 5]);
+    nbt.extend(b"Level"); // TAG_Compound "Level"
+    nbt.extend([3u8, 0, 4]);
+    nbt.extend(b"xPos");
+    nbt.extend(5i32.to_be_bytes()); // TAG_Int "xPos": 5
+    nbt.extend([3u8, 0, 4]);
+    nbt.extend(b"zPos");
+    nbt.extend(7i32.to_be_bytes()); // TAG_Int "zPos": 7
+    nbt.push(0); // end Level
+    nbt.push(0); // end root
+    nbt
+}
+
+#[test]
+fn repair_zeroes_only_the_flagged_chunks_table_entries() {
+    let schema = AnvilSchema::default();
+    let mut region = blank_region(&schema);
+    region.chunks[0] = RawChunk::from_decompressed(&healthy_level_nbt(), Compression::ZLib);
+    region.chunks[1] = RawChunk::from_decompressed(&healthy_level_nbt(), Compression::ZLib);
+
+    let (mut file, _) = region.to_file(&schema).expect("two small chunks should pack inline");
+    let packed = RawRegion::from_file(&file, &schema).expect("should unpack what this crate packed");
+
+    // Corrupt chunk 1's compression byte so scan flags it, leaving chunk 0 untouched.
+    let corrupt_start = packed.chunks[1].sector_offset * schema.pos_multiplier;
+    file[corrupt_start + 4] = 200;
+
+    let repaired = repair(&file, &schema, RepairMode::DeleteCorruptChunks);
+    let region = RawRegion::from_file(&repaired, &schema).expect("a repaired region should still unpack");
+
+    assert_eq!(region.chunks[0].decompress().unwrap(), healthy_level_nbt());
+    assert!(region.chunks[1].data.is_empty());
+    assert_eq!(region.chunks[1].timestamp, 0);
+    assert_eq!(region.chunks[1].sector_offset, 0);
 }